@@ -1,6 +1,5 @@
 use std::collections::HashSet;
 use std::{
-    io::{ErrorKind, Result}, 
     fs::{OpenOptions, rename}
 };
 use std::path::PathBuf;
@@ -8,22 +7,193 @@ use std::env;
 
 use clap::{Parser, Subcommand};
 
-#[derive(Debug, Clone)]
-struct CsvIndexError;
+/// The error type threaded through every fallible operation in this crate.
+/// Unlike a bare `io::Error`, it keeps enough context (file path, line
+/// number, offending value) to tell a user exactly where a corrupt
+/// `todo_list.csv` went wrong instead of a terse one-liner.
+#[derive(Debug)]
+enum AppError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    /// A record at `line` of `path` failed to deserialize.
+    Record { path: PathBuf, line: usize, source: csv::Error },
+    /// `check_file_get_last` found a non-contiguous index while validating `path`.
+    IndexGap { path: PathBuf, line: usize, expected: u32, found: u32 },
+}
+
+impl AppError {
+    fn record(path: &PathBuf, line: usize, source: csv::Error) -> AppError {
+        return AppError::Record { path: path.clone(), line, source };
+    }
+
+    fn index_gap(path: &PathBuf, line: usize, expected: u32, found: u32) -> AppError {
+        return AppError::IndexGap { path: path.clone(), line, expected, found };
+    }
+}
 
-impl std::fmt::Display for CsvIndexError {
+impl std::fmt::Display for AppError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "incorrect index found")
+        return match self {
+            AppError::Io(e) => write!(f, "{e}"),
+            AppError::Csv(e) => write!(f, "{e}"),
+            AppError::Record { path, line, .. } => {
+                write!(f, "{}: line {}: failed to parse record", path.display(), line)
+            }
+            AppError::IndexGap { path, line, expected, found } => {
+                write!(f, "{}: line {}: expected index {}, found {}", path.display(), line, expected, found)
+            }
+        };
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            AppError::Io(e) => Some(e),
+            AppError::Csv(e) => Some(e),
+            AppError::Record { source, .. } => Some(source),
+            AppError::IndexGap { .. } => None,
+        };
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> AppError {
+        return AppError::Io(e);
+    }
+}
+
+impl From<csv::Error> for AppError {
+    fn from(e: csv::Error) -> AppError {
+        return AppError::Csv(e);
+    }
+}
+
+type Result<T> = std::result::Result<T, AppError>;
+
+/// The version marker written as the first line of `todo_list.csv`, ahead of the
+/// data rows, so that pre-`priority`/`due` 3-column files can be detected and
+/// upgraded in place instead of failing to parse.
+const VERSION_MARKER: &str = "#v2";
+
+/// A pluggable conversion applied to a raw CSV cell before it is handed to the
+/// typed field it backs. `Timestamp`/`TimestampFmt` normalize a date cell to
+/// `%Y-%m-%d` (optionally via a caller-supplied format), while `Integer`/`Bytes`
+/// just validate that the cell parses as a number. An empty cell always yields
+/// `None` rather than an error, since `priority`/`due` are optional columns.
+enum Conversion {
+    Bytes,
+    Integer,
+    Timestamp,
+    TimestampFmt(String),
+}
+
+impl std::str::FromStr for Conversion {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        return Ok(match s {
+            "bytes" => Conversion::Bytes,
+            "integer" => Conversion::Integer,
+            "timestamp" => Conversion::Timestamp,
+            fmt => Conversion::TimestampFmt(fmt.to_string()),
+        });
     }
 }
 
-impl std::error::Error for CsvIndexError {}
+impl Conversion {
+    fn convert(&self, raw: &str) -> Option<String> {
+        if raw.is_empty() {
+            return None;
+        }
+        return match self {
+            Conversion::Bytes => raw.parse::<u64>().ok().map(|v| v.to_string()),
+            Conversion::Integer => raw.parse::<i64>().ok().map(|v| v.to_string()),
+            Conversion::Timestamp => chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+            Conversion::TimestampFmt(fmt) => chrono::NaiveDate::parse_from_str(raw, fmt)
+                .ok()
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+        };
+    }
+}
+
+/// `serde(with = ...)` module backing `Record::priority`, routing the raw cell
+/// through `Conversion::Integer` so an empty cell becomes `None`.
+mod priority_opt {
+    use super::Conversion;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &Option<u8>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        return match value {
+            Some(v) => serializer.serialize_str(&v.to_string()),
+            None => serializer.serialize_str(""),
+        };
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Option<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        return match Conversion::Integer.convert(&raw) {
+            Some(v) => v.parse::<u8>().map(Some).map_err(serde::de::Error::custom),
+            None => Ok(None),
+        };
+    }
+}
+
+/// `serde(with = ...)` module backing `Record::due`, routing the raw cell
+/// through `Conversion::Timestamp` so an empty cell becomes `None`.
+mod naive_date_opt {
+    use super::Conversion;
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(date: &Option<NaiveDate>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        return match date {
+            Some(d) => serializer.serialize_str(&d.format("%Y-%m-%d").to_string()),
+            None => serializer.serialize_str(""),
+        };
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Option<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        return match Conversion::Timestamp.convert(&raw) {
+            Some(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d").map(Some).map_err(serde::de::Error::custom),
+            None => Ok(None),
+        };
+    }
+}
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 struct Record {
     index: u32,
     action: String,
     done: bool,
+    #[serde(default, with = "priority_opt")]
+    priority: Option<u8>,
+    #[serde(default, with = "naive_date_opt")]
+    due: Option<chrono::NaiveDate>,
+}
+
+/// A pre-`priority`/`due` record, used only to read legacy 3-column files
+/// during the upgrade to the versioned header.
+#[derive(Debug, serde::Deserialize)]
+struct LegacyRecord {
+    index: u32,
+    action: String,
+    done: bool,
 }
 
 impl std::fmt::Display for Record {
@@ -63,6 +233,14 @@ enum Commands {
     Add {
         #[arg(action = clap::ArgAction::Append)]
         names: Vec<String>,
+
+        /// due date for the added todo(s), e.g. 2025-06-01
+        #[arg(long)]
+        due: Option<chrono::NaiveDate>,
+
+        /// priority for the added todo(s)
+        #[arg(short, long)]
+        priority: Option<u8>,
     },
 
     /// removes indexes from the list
@@ -92,6 +270,52 @@ enum Commands {
     },
 
     Reset,
+
+    /// finds todos matching a query, tolerating small typos
+    Find {
+        query: String,
+    },
+
+    /// orders the list by due date or priority
+    Sort {
+        #[arg(value_enum)]
+        by: SortKey,
+    },
+
+    /// combines the list with another todo CSV
+    Merge {
+        other: PathBuf,
+
+        #[arg(value_enum, short, long, default_value_t = MergeMode::Outer)]
+        mode: MergeMode,
+    },
+}
+
+/// The field `Sort` orders the list by. `Priority` sorts ascending (P0-style:
+/// lower number is more urgent and sorts first); `Due` sorts soonest-first.
+/// Items missing the field sort last in both cases.
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum SortKey {
+    Due,
+    Priority,
+}
+
+/// How `Merge` combines the current list with another one.
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum MergeMode {
+    /// keep every action from both lists, deduped on the normalized action text
+    Outer,
+    /// keep only actions present in both lists
+    Intersect,
+}
+
+impl std::fmt::Display for MergeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", match self {
+            MergeMode::Outer => "outer",
+            MergeMode::Intersect => "intersect",
+        });
+    }
 }
 
 fn no_header_reader() -> csv::ReaderBuilder {
@@ -106,18 +330,35 @@ fn no_header_writer() -> csv::WriterBuilder {
     return res;
 }
 
+/// Opens `path` for reading its data rows, skipping the leading `VERSION_MARKER`
+/// line written by `check_file_get_last`/`upgrade_if_legacy`.
+fn data_reader(path: &PathBuf) -> Result<csv::Reader<std::io::BufReader<std::fs::File>>> {
+    use std::io::BufRead;
+
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut first_line = String::new();
+    reader.read_line(&mut first_line)?;
+    if first_line.trim_end() == VERSION_MARKER {
+        return Ok(no_header_reader().from_reader(reader));
+    }
+
+    // No marker: the first line is already a data row (legacy file not yet upgraded).
+    let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    return Ok(no_header_reader().from_reader(reader));
+}
+
 fn list_todos(path: PathBuf) -> Result<()> {
-    let mut rdr = no_header_reader().from_path(path)?;
+    let mut rdr = data_reader(&path)?;
     for result in rdr.deserialize() {
         let record: Record = result?;
         println!("{}", record);
     }
 
-    
+
     return Ok(());
 }
 
-fn add_records(path: PathBuf, actions: &Vec<String>, i: u32) -> Result<()> {
+fn add_records(path: PathBuf, actions: &Vec<String>, i: u32, priority: Option<u8>, due: Option<chrono::NaiveDate>) -> Result<()> {
     let mut writer = no_header_writer()
         .from_writer(OpenOptions::new()
             .write(true)
@@ -130,6 +371,8 @@ fn add_records(path: PathBuf, actions: &Vec<String>, i: u32) -> Result<()> {
             index: last,
             action: action.to_string(),
             done: false,
+            priority,
+            due,
         })?;
         last += 1;
     }
@@ -141,17 +384,20 @@ fn add_records(path: PathBuf, actions: &Vec<String>, i: u32) -> Result<()> {
 type Rp = fn(u32, &Record) -> Option<Record>;
 
 fn file_map(path: &PathBuf, indexes: &mut HashSet<u32>, all: bool, f: Rp) -> Result<()> {
+    use std::io::Write as _;
+
     let mut i: u32 = 1;
     let mut aux: PathBuf = path.iter().collect();
     aux.pop();
     aux.push("aux.csv");
-    let mut rdr = no_header_reader().from_path(path)?;
-    let mut writer = no_header_writer()
-        .from_writer(OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(&aux)?
-        );
+    let mut rdr = data_reader(path)?;
+    let mut aux_file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&aux)?;
+    writeln!(aux_file, "{VERSION_MARKER}")?;
+    let mut writer = no_header_writer().from_writer(aux_file);
     for result in rdr.deserialize() {
         let record: Record = result?;
         if all || indexes.remove(&record.index) {
@@ -165,6 +411,8 @@ fn file_map(path: &PathBuf, indexes: &mut HashSet<u32>, all: bool, f: Rp) -> Res
                 index: i,
                 action: record.action,
                 done: record.done,
+                priority: record.priority,
+                due: record.due,
             })?;
         }
         i += 1;
@@ -175,25 +423,239 @@ fn file_map(path: &PathBuf, indexes: &mut HashSet<u32>, all: bool, f: Rp) -> Res
 }
 
 fn rm_records(path: &PathBuf, indexes: &mut HashSet<u32>) -> Result<()> {
-    return 
-        file_map(path, indexes, false, 
-        |_, _| None, 
+    return
+        file_map(path, indexes, false,
+        |_, _| None,
         );
 }
 
+/// Byte length of the `VERSION_MARKER` line (marker + trailing newline) that
+/// precedes the data rows, i.e. the offset the sidecar index is relative to.
+fn marker_len() -> u64 {
+    return (VERSION_MARKER.len() + 1) as u64;
+}
+
+/// Sidecar byte-offset index (`<csv>.idx`) giving O(1) seek access to a
+/// record's line, so a single-index `Done`/`Undo` can rewrite just that line
+/// instead of paying `file_map`'s full rewrite of every record.
+mod index {
+    use super::{data_reader, marker_len, Record, Result, Rp};
+    use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+    use std::fs::{File, OpenOptions};
+    use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+    use std::path::PathBuf;
+
+    pub struct Indexed {
+        path: PathBuf,
+        offsets: Vec<u64>,
+    }
+
+    fn idx_path(csv_path: &PathBuf) -> PathBuf {
+        let mut idx = csv_path.clone();
+        idx.set_extension("idx");
+        return idx;
+    }
+
+    fn rebuild(csv_path: &PathBuf, idx_path: &PathBuf) -> Result<Vec<u64>> {
+        let mut reader = data_reader(csv_path)?;
+        let mut offsets = Vec::new();
+        let mut record = csv::ByteRecord::new();
+        loop {
+            let position = reader.position().clone();
+            if !reader.read_byte_record(&mut record)? {
+                break;
+            }
+            offsets.push(position.byte());
+        }
+
+        let mut idx_file = OpenOptions::new().create(true).write(true).truncate(true).open(idx_path)?;
+        for offset in &offsets {
+            idx_file.write_u64::<BigEndian>(*offset)?;
+        }
+        return Ok(offsets);
+    }
+
+    impl Indexed {
+        /// Opens the sidecar index for `csv_path`, rebuilding it first if the
+        /// CSV has been modified more recently than the index.
+        pub fn open(csv_path: &PathBuf) -> Result<Indexed> {
+            let idx_path = idx_path(csv_path);
+            let csv_modified = std::fs::metadata(csv_path)?.modified()?;
+            let stale = match std::fs::metadata(&idx_path).and_then(|m| m.modified()) {
+                Ok(idx_modified) => csv_modified > idx_modified,
+                Err(_) => true,
+            };
+
+            let offsets = if stale {
+                rebuild(csv_path, &idx_path)?
+            } else {
+                let mut idx_file = File::open(&idx_path)?;
+                let mut offsets = Vec::new();
+                while let Ok(offset) = idx_file.read_u64::<BigEndian>() {
+                    offsets.push(offset);
+                }
+                offsets
+            };
+
+            return Ok(Indexed { path: csv_path.clone(), offsets });
+        }
+
+        pub fn len(&self) -> usize {
+            return self.offsets.len();
+        }
+
+        /// Reads the raw CSV line (including its trailing newline) for the
+        /// 1-based record `index`.
+        fn seek(&self, index: u32) -> Result<String> {
+            let offset = self.offsets[(index - 1) as usize];
+            let mut file = File::open(&self.path)?;
+            file.seek(SeekFrom::Start(marker_len() + offset))?;
+            let mut line = String::new();
+            BufReader::new(file).read_line(&mut line)?;
+            return Ok(line);
+        }
+
+        /// Applies `f` to the record at `index` and rewrites just that line in
+        /// place, splicing the remainder of the file if the new line is a
+        /// different length. Returns `false` (without touching the file) if
+        /// `f` drops the record, since that reindexes the rest of the list
+        /// and has to go through `file_map` instead.
+        pub fn rewrite(&self, index: u32, f: Rp) -> Result<bool> {
+            let raw = self.seek(index)?;
+            let record: Record = {
+                let mut rdr = csv::ReaderBuilder::new().has_headers(false).from_reader(raw.as_bytes());
+                match rdr.deserialize().next() {
+                    Some(result) => result?,
+                    None => return Ok(false),
+                }
+            };
+            let updated = match f(record.index, &record) {
+                Some(rec) => rec,
+                None => return Ok(false),
+            };
+            let line = {
+                let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+                wtr.serialize(&updated)?;
+                let bytes = wtr.into_inner().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+                String::from_utf8(bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
+            };
+
+            let offset = marker_len() + self.offsets[(index - 1) as usize];
+            let old_len = raw.len() as u64;
+            let new_len = line.len() as u64;
+            let mut file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+            if new_len == old_len {
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(line.as_bytes())?;
+            } else {
+                let mut tail = Vec::new();
+                file.seek(SeekFrom::Start(offset + old_len))?;
+                file.read_to_end(&mut tail)?;
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(line.as_bytes())?;
+                file.write_all(&tail)?;
+                file.set_len(offset + new_len + tail.len() as u64)?;
+
+                // The splice shifted every offset after this record, but the sidecar
+                // still holds the pre-splice ones. A same-tick rewrite right after this
+                // would see an unchanged mtime and trust those stale offsets, so drop
+                // the sidecar outright rather than relying on mtime ordering for
+                // correctness; the next `open` rebuilds it from scratch.
+                let _ = std::fs::remove_file(idx_path(&self.path));
+            }
+
+            return Ok(true);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Indexed;
+        use crate::{check_file_get_last, VERSION_MARKER};
+        use std::path::PathBuf;
+
+        fn temp_csv_path(name: &str) -> PathBuf {
+            let mut path = std::env::temp_dir();
+            path.push(format!("todo_app_test_{name}_{}.csv", std::process::id()));
+            return path;
+        }
+
+        /// Two consecutive single-index toggles each go through the
+        /// length-changing splice branch of `rewrite` (`false` is one byte
+        /// longer than `true`, so each rewritten line shrinks), which used to leave
+        /// a stale sidecar index for the second toggle to trust. Both writes
+        /// should land cleanly and the file should still reindex contiguously.
+        #[test]
+        fn back_to_back_fast_path_toggles_stay_contiguous() {
+            let path = temp_csv_path("back_to_back");
+            let idx_path = path.with_extension("idx");
+            let _ = std::fs::remove_file(&path);
+            let _ = std::fs::remove_file(&idx_path);
+
+            std::fs::write(
+                &path,
+                format!(
+                    "{VERSION_MARKER}\n1,one,false,,\n2,two,false,,\n3,three,false,,\n"
+                ),
+            )
+            .unwrap();
+
+            let mark_done: crate::Rp = |i, r| {
+                Some(crate::Record { index: i, action: r.action.clone(), done: true, priority: r.priority, due: r.due })
+            };
+
+            let indexed = Indexed::open(&path).unwrap();
+            assert!(indexed.rewrite(1, mark_done).unwrap());
+
+            let indexed = Indexed::open(&path).unwrap();
+            assert!(indexed.rewrite(2, mark_done).unwrap());
+
+            let last = check_file_get_last(&path).unwrap();
+            assert_eq!(last, 4);
+
+            let _ = std::fs::remove_file(&path);
+            let _ = std::fs::remove_file(&idx_path);
+        }
+    }
+}
 
 fn mark_records(path: &PathBuf, indexes: &mut HashSet<u32>, all: bool) -> Result<()> {
-    return 
-        file_map(path, indexes, all, 
-        |i, r| Some(Record { index: i, action: r.action.clone(), done: true}), 
-        );
+    let f: Rp = |i, r| Some(Record { index: i, action: r.action.clone(), done: true, priority: r.priority, due: r.due});
+    if !all {
+        if let Some(&single) = single_index(indexes) {
+            if let Ok(indexed) = index::Indexed::open(path) {
+                if single >= 1 && single as usize <= indexed.len() && indexed.rewrite(single, f)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    return file_map(path, indexes, all, f);
 }
 
 fn unmark_records(path: &PathBuf, indexes: &mut HashSet<u32>, all: bool) -> Result<()> {
-    return 
-        file_map(path, indexes, all, 
-        |i, r| Some(Record { index: i, action: r.action.clone(), done: false}), 
-        );
+    let f: Rp = |i, r| Some(Record { index: i, action: r.action.clone(), done: false, priority: r.priority, due: r.due});
+    if !all {
+        if let Some(&single) = single_index(indexes) {
+            if let Ok(indexed) = index::Indexed::open(path) {
+                if single >= 1 && single as usize <= indexed.len() && indexed.rewrite(single, f)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    return file_map(path, indexes, all, f);
+}
+
+/// Returns the lone member of `indexes` when it holds exactly one index, so
+/// `Done`/`Undo` can take the `index::Indexed` fast path instead of
+/// `file_map`'s full rewrite.
+fn single_index(indexes: &HashSet<u32>) -> Option<&u32> {
+    let mut iter = indexes.iter();
+    return match (iter.next(), iter.next()) {
+        (Some(only), None) => Some(only),
+        _ => None,
+    };
 }
 
 fn reset_records(path: &PathBuf) -> Result<()> {
@@ -203,24 +665,194 @@ fn reset_records(path: &PathBuf) -> Result<()> {
         );
 }
 
+// Classic two-row edit-distance table: O(len(a)*len(b)) time, O(min(len(a), len(b))) space.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let shorter: Vec<char> = shorter.chars().collect();
+    let longer: Vec<char> = longer.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=shorter.len()).collect();
+    let mut curr: Vec<usize> = vec![0; shorter.len() + 1];
+
+    for i in 1..=longer.len() {
+        curr[0] = i;
+        for j in 1..=shorter.len() {
+            let cost = if longer[i - 1] == shorter[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    return prev[shorter.len()];
+}
+
+fn find_records(path: PathBuf, query: &str) -> Result<()> {
+    let query = query.to_lowercase();
+    let max_dist = query.chars().count() / 4 + 1;
+
+    let mut rdr = data_reader(&path)?;
+    let mut hits: Vec<(Record, bool, usize)> = Vec::new();
+    for result in rdr.deserialize() {
+        let record: Record = result?;
+        let action = record.action.to_lowercase();
+        if action.contains(&query) {
+            hits.push((record, true, 0));
+            continue;
+        }
+        let best = action
+            .split_whitespace()
+            .map(|word| levenshtein(&query, word))
+            .filter(|dist| *dist <= max_dist)
+            .min();
+        if let Some(dist) = best {
+            hits.push((record, false, dist));
+        }
+    }
+
+    hits.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+    for (record, _, _) in hits {
+        println!("{}", record);
+    }
+
+    return Ok(());
+}
+
+/// Rewrites a pre-`priority`/`due` 3-column file (no `VERSION_MARKER` line) into
+/// the current versioned format, leaving already-upgraded files untouched.
+fn upgrade_if_legacy(path: &PathBuf) -> Result<()> {
+    use std::io::{BufRead, Write as _};
+
+    let mut first_line = String::new();
+    std::io::BufReader::new(std::fs::File::open(path)?).read_line(&mut first_line)?;
+    if first_line.trim_end() == VERSION_MARKER {
+        return Ok(());
+    }
+
+    let mut aux: PathBuf = path.iter().collect();
+    aux.pop();
+    aux.push("aux.csv");
+    let mut legacy_rdr = no_header_reader().from_path(path)?;
+    let mut aux_file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&aux)?;
+    writeln!(aux_file, "{VERSION_MARKER}")?;
+    let mut writer = no_header_writer().from_writer(aux_file);
+    for result in legacy_rdr.deserialize() {
+        let legacy: LegacyRecord = result?;
+        writer.serialize(Record {
+            index: legacy.index,
+            action: legacy.action,
+            done: legacy.done,
+            priority: None,
+            due: None,
+        })?;
+    }
+    writer.flush()?;
+    rename(aux.as_path(), path.as_path())?;
+
+    return Ok(());
+}
+
 fn check_file_get_last(path: &PathBuf) -> Result<u32> {
     if !path.exists() {
-        std::fs::File::create(path)?;
+        std::fs::write(path, format!("{VERSION_MARKER}\n"))?;
         return Ok(1);
     }
-    let mut i = 1;
-    let mut rdr = no_header_reader().from_path(path)?;
+    upgrade_if_legacy(path)?;
+    let mut i: u32 = 1;
+    let mut rdr = data_reader(path)?;
     for result in rdr.deserialize() {
-        let record: Record = result?;
+        // `i + 1` accounts for the VERSION_MARKER line taking up line 1 of the file.
+        let record: Record = result.map_err(|source| AppError::record(path, (i + 1) as usize, source))?;
         if i != record.index {
-            return Err(std::io::Error::new(ErrorKind::Other, CsvIndexError));
+            return Err(AppError::index_gap(path, (i + 1) as usize, i, record.index));
         }
         i += 1;
     }
     return Ok(i);
 }
 
-fn main() -> Result<()> {
+fn read_all(path: &PathBuf) -> Result<Vec<Record>> {
+    let mut rdr = data_reader(path)?;
+    let mut records = Vec::new();
+    for result in rdr.deserialize() {
+        records.push(result?);
+    }
+    return Ok(records);
+}
+
+/// Writes `records` through `aux.csv` with fresh 1-based indexes, the same
+/// reindexing dance `file_map` does, then renames it over `path`.
+fn rewrite_all(path: &PathBuf, records: Vec<Record>) -> Result<()> {
+    use std::io::Write as _;
+
+    let mut aux: PathBuf = path.iter().collect();
+    aux.pop();
+    aux.push("aux.csv");
+    let mut aux_file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&aux)?;
+    writeln!(aux_file, "{VERSION_MARKER}")?;
+    let mut writer = no_header_writer().from_writer(aux_file);
+    for (i, record) in records.into_iter().enumerate() {
+        writer.serialize(Record { index: (i + 1) as u32, ..record })?;
+    }
+    writer.flush()?;
+    rename(aux.as_path(), path.as_path())?;
+
+    return Ok(());
+}
+
+fn sort_records(path: &PathBuf, by: &SortKey) -> Result<()> {
+    let mut records = read_all(path)?;
+    match by {
+        SortKey::Due => records.sort_by_key(|r| (r.due.is_none(), r.due)),
+        SortKey::Priority => records.sort_by_key(|r| (r.priority.is_none(), r.priority)),
+    }
+
+    return rewrite_all(path, records);
+}
+
+fn normalized_action(action: &str) -> String {
+    return action.trim().to_lowercase();
+}
+
+fn merge_records(path: &PathBuf, other: &PathBuf, mode: &MergeMode) -> Result<()> {
+    let mut current = read_all(path)?;
+    let other_records = read_all(other)?;
+
+    match mode {
+        MergeMode::Outer => {
+            let mut seen: HashSet<String> = current.iter().map(|r| normalized_action(&r.action)).collect();
+            for other_record in other_records {
+                let key = normalized_action(&other_record.action);
+                match current.iter_mut().find(|r| normalized_action(&r.action) == key) {
+                    Some(existing) => existing.done = existing.done || other_record.done,
+                    None => if seen.insert(key) {
+                        current.push(other_record);
+                    },
+                }
+            }
+        }
+        MergeMode::Intersect => {
+            let other_keys: HashSet<String> = other_records.iter().map(|r| normalized_action(&r.action)).collect();
+            for record in current.iter_mut() {
+                if let Some(other_record) = other_records.iter().find(|o| normalized_action(&o.action) == normalized_action(&record.action)) {
+                    record.done = record.done || other_record.done;
+                }
+            }
+            current.retain(|r| other_keys.contains(&normalized_action(&r.action)));
+        }
+    }
+
+    return rewrite_all(path, current);
+}
+
+fn run() -> Result<()> {
     let cli = Cli::parse();
 
     // You can check the value provided by positional arguments, or option arguments
@@ -244,7 +876,7 @@ fn main() -> Result<()> {
     } 
 
     return match &cli.command.unwrap() {
-        Commands::Add { names } => add_records(path, names, last_index),
+        Commands::Add { names, due, priority } => add_records(path, names, last_index, *priority, *due),
         Commands::Rm { indexes } => {
             rm_records(&path, &mut HashSet::from_iter(indexes.iter().cloned()), )
         },
@@ -257,7 +889,22 @@ fn main() -> Result<()> {
         Commands::Reset => {
             reset_records(&path)
         }
+        Commands::Find { query } => find_records(path, query),
+        Commands::Sort { by } => sort_records(&path, by),
+        Commands::Merge { other, mode } => merge_records(&path, other, mode),
         _ => Ok(()),
 
     }
 }
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {err}");
+        let mut cause = std::error::Error::source(&err);
+        while let Some(err) = cause {
+            eprintln!("caused by: {err}");
+            cause = err.source();
+        }
+        std::process::exit(1);
+    }
+}